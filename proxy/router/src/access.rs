@@ -18,6 +18,13 @@ pub struct Node<T> {
 ///
 /// When the guard is dropped, the value's `last_access` time is updated with the provided
 /// time source.
+///
+/// `Access`/`Node` only track a timestamp; they don't know about any
+/// idle-expiration index (e.g. `crate::timing_wheel::TimingWheel`) a
+/// collection built on top of them might keep. A collection that wants
+/// proactive idle eviction (see `crate::cache::Cache`) reschedules its own
+/// wheel deadline around each `Access` it hands out, rather than `Access`
+/// doing so itself.
 pub struct Access<'a, T: 'a, N: Now + 'a = ()> {
     node: &'a mut Node<T>,
     now: &'a N,
@@ -64,6 +71,11 @@ impl<T> Node<T> {
     pub fn last_access(&self) -> Instant {
         self.last_access
     }
+
+    /// Unwraps the node, discarding its access time.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
 }
 
 impl<T> Deref for Node<T> {
@@ -0,0 +1,10 @@
+//! Shared building blocks for the proxy's caches: access-time tracking and
+//! the scheduling primitives used to expire entries that have gone idle.
+
+pub mod access;
+pub mod cache;
+pub mod reaper;
+pub mod shared;
+pub mod timing_wheel;
+
+pub use access::Now;
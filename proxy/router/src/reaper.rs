@@ -0,0 +1,167 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+use crate::timing_wheel::TimingWheel;
+use crate::Now;
+
+/// Proactively reaps idle entries from a [`TimingWheel`] rather than
+/// waiting for the next insert to evict them.
+///
+/// Sleeps until the wheel's next deadline, or parks on [`Handle::notify`]
+/// when nothing is scheduled. Wakeups are treated as possibly spurious or
+/// early, so `now` is always rechecked against the deadline before reaping.
+///
+/// The wheel only tracks keys, not the values they index; `on_expired` is
+/// called with each expired key so the caller can evict it from whatever
+/// it's actually backing, e.g. `crate::cache::Cache::remove`.
+pub struct Reaper<T, N: Now = (), F: FnMut(T) = fn(T)> {
+    wheel: Arc<Mutex<TimingWheel<T>>>,
+    now: N,
+    notify: Arc<Notify>,
+    on_expired: F,
+}
+
+/// Wakes a running [`Reaper`] so it re-checks the wheel's deadline, e.g.
+/// after inserting an entry that expires sooner than the one the reaper is
+/// currently sleeping until.
+#[derive(Clone)]
+pub struct Handle(Arc<Notify>);
+
+// ===== impl Reaper =====
+
+impl<T, N: Now, F: FnMut(T)> Reaper<T, N, F> {
+    /// Creates a driver over `wheel`, returning it alongside a [`Handle`]
+    /// that can be used to wake it when a fresher deadline is inserted.
+    ///
+    /// `on_expired` is invoked once per key as it expires, so the caller can
+    /// evict it from the collection the wheel is scheduling on behalf of.
+    pub fn new(wheel: Arc<Mutex<TimingWheel<T>>>, now: N, on_expired: F) -> (Self, Handle) {
+        let notify = Arc::new(Notify::new());
+        let handle = Handle(notify.clone());
+        (
+            Reaper {
+                wheel,
+                now,
+                notify,
+                on_expired,
+            },
+            handle,
+        )
+    }
+
+    /// Runs the reap loop forever. Intended to be spawned as a background
+    /// task alongside the cache it's reaping.
+    pub async fn run(mut self) -> ! {
+        loop {
+            self.reap_once().await;
+        }
+    }
+
+    /// Waits for the wheel's next deadline (or a notification that one was
+    /// just scheduled), then drops whatever has expired once `now` catches
+    /// up to it.
+    async fn reap_once(&mut self) {
+        loop {
+            let deadline = self.wheel.lock().unwrap().next_expiration();
+            let deadline = match deadline {
+                Some(deadline) => deadline,
+                // Nothing scheduled: park until an insert nudges us.
+                None => {
+                    self.notify.notified().await;
+                    continue;
+                }
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(deadline.saturating_duration_since(self.now.now())) => {}
+                _ = self.notify.notified() => {
+                    // A fresher (possibly earlier) deadline may have just
+                    // been inserted; re-check rather than assuming we woke
+                    // for the deadline we were sleeping on.
+                    continue;
+                }
+            }
+
+            let now = self.now.now();
+            if now < deadline {
+                // Spurious or early wakeup: loop back, which re-sleeps for
+                // the (recomputed) remaining time until the deadline.
+                continue;
+            }
+
+            let expired = self.wheel.lock().unwrap().poll(&self.now);
+            for key in expired {
+                (self.on_expired)(key);
+            }
+            return;
+        }
+    }
+}
+
+// ===== impl Handle =====
+
+impl Handle {
+    /// Wakes the reaper so it re-checks the wheel for a new deadline.
+    pub fn notify(&self) {
+        self.0.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use test_util::*;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn reaps_entry_once_deadline_elapses() {
+        let mut clock = Clock::default();
+        let wheel = Arc::new(Mutex::new(TimingWheel::new(&clock)));
+        wheel
+            .lock()
+            .unwrap()
+            .insert(clock.now() + Duration::from_millis(10), "a");
+
+        let expired = Arc::new(Mutex::new(Vec::new()));
+        let on_expired = {
+            let expired = expired.clone();
+            move |key| expired.lock().unwrap().push(key)
+        };
+        let (mut reaper, _handle) = Reaper::new(wheel.clone(), clock.clone(), on_expired);
+        let reap = tokio::spawn(async move { reaper.reap_once().await });
+
+        clock.advance(Duration::from_millis(10));
+        tokio::time::advance(Duration::from_millis(10)).await;
+
+        reap.await.unwrap();
+        assert!(wheel.lock().unwrap().next_expiration().is_none());
+        assert_eq!(*expired.lock().unwrap(), vec!["a"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn notify_re_arms_driver_parked_on_empty_wheel() {
+        let mut clock = Clock::default();
+        let wheel: Arc<Mutex<TimingWheel<&str>>> = Arc::new(Mutex::new(TimingWheel::new(&clock)));
+
+        let (mut reaper, handle) = Reaper::new(wheel.clone(), clock.clone(), |_key| {});
+        let reap = tokio::spawn(async move { reaper.reap_once().await });
+
+        // Let the driver observe the empty wheel and park.
+        tokio::task::yield_now().await;
+
+        wheel
+            .lock()
+            .unwrap()
+            .insert(clock.now() + Duration::from_millis(5), "a");
+        handle.notify();
+
+        clock.advance(Duration::from_millis(5));
+        tokio::time::advance(Duration::from_millis(5)).await;
+
+        reap.await.unwrap();
+        assert!(wheel.lock().unwrap().next_expiration().is_none());
+    }
+}
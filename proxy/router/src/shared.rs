@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+
+use crate::Now;
+
+/// A `T`-typed value behind an atomically swappable pointer.
+///
+/// Unlike `Access`, which takes `&mut Node`, `load` never blocks a
+/// concurrent `rcu`. Writers clone the current value, apply their
+/// mutation, and swap the result in, retrying on contention.
+///
+/// `Shared` keeps its own `last_access_ms` rather than wrapping a
+/// `crate::access::Node`, because `Node::access` requires `&mut self` and
+/// would defeat the point of a lock-free, concurrently-readable value.
+/// It's a standalone primitive, not a `Node` substitute: a `Cache<K, V>`
+/// entry is a `Node<V>`, not a `Node<Shared<V>>`, so the two don't compose
+/// and a caller picks one or the other per value.
+pub struct Shared<T, N: Now = ()> {
+    value: ArcSwap<T>,
+    start: Instant,
+    /// `last_access`, as a millisecond offset from `start`, so it can be
+    /// updated with a plain atomic store rather than a lock.
+    last_access_ms: AtomicU64,
+    now: N,
+}
+
+impl<T, N: Now> Shared<T, N> {
+    pub fn new(value: T, now: N) -> Self {
+        let start = now.now();
+        Shared {
+            value: ArcSwap::from_pointee(value),
+            start,
+            last_access_ms: AtomicU64::new(0),
+            now,
+        }
+    }
+
+    /// Returns a cheap, reference-counted snapshot of the current value and
+    /// records the read as an access. Never blocks.
+    pub fn load(&self) -> Arc<T> {
+        self.touch();
+        self.value.load_full()
+    }
+
+    /// Applies `f` to a clone of the current value and atomically installs
+    /// the result, retrying if another writer raced ahead in the meantime.
+    pub fn rcu<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> T,
+    {
+        // `ArcSwap::rcu` hands the closure `&Arc<T>`, not `&T`, and wants
+        // the replacement wrapped back up in an `Arc`.
+        let _ = self.value.rcu(|old: &Arc<T>| Arc::new(f(&**old)));
+        self.touch();
+    }
+
+    /// Returns the last time the value was loaded or swapped.
+    pub fn last_access(&self) -> Instant {
+        self.start + Duration::from_millis(self.last_access_ms.load(Ordering::Relaxed))
+    }
+
+    /// Atomically stores the current time as `last_access`.
+    fn touch(&self) {
+        let ms = self
+            .now
+            .now()
+            .saturating_duration_since(self.start)
+            .as_millis() as u64;
+        self.last_access_ms.store(ms, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use test_util::*;
+
+    use super::*;
+
+    #[test]
+    fn load_observes_value_installed_by_rcu() {
+        let clock = Clock::default();
+        let shared = Shared::new(vec![1, 2, 3], clock.clone());
+
+        assert_eq!(*shared.load(), vec![1, 2, 3]);
+
+        shared.rcu(|old| {
+            let mut new = old.clone();
+            new.push(4);
+            new
+        });
+
+        assert_eq!(*shared.load(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn last_access_updated_on_load_and_rcu() {
+        let mut clock = Clock::default();
+        let t0 = clock.now();
+        let shared = Shared::new(0, clock.clone());
+        assert_eq!(shared.last_access(), t0);
+
+        clock.advance(Duration::from_millis(1));
+        shared.load();
+        let t1 = shared.last_access();
+        assert_ne!(t0, t1);
+
+        clock.advance(Duration::from_millis(1));
+        shared.rcu(|old| old + 1);
+        assert_ne!(t1, shared.last_access());
+    }
+}
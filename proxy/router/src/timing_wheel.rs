@@ -0,0 +1,282 @@
+use std::time::{Duration, Instant};
+
+use crate::Now;
+
+/// Number of levels in the wheel. Level 0 has the finest granularity and
+/// each subsequent level covers `SLOTS` times the span of the one below it.
+const LEVELS: usize = 6;
+
+/// Number of slots per level.
+const SLOTS: usize = 64;
+
+/// `log2(SLOTS)`, i.e. the number of bits of the deadline each level consumes.
+const SLOT_BITS: u32 = 6;
+
+/// The granularity of level 0, in milliseconds.
+const TICK_MS: u64 = 1;
+
+/// Schedules values by expiration deadline across `LEVELS` levels of
+/// `SLOTS` slots each, so that finding what's expired doesn't require
+/// scanning every scheduled entry.
+pub struct TimingWheel<T> {
+    levels: [Level<T>; LEVELS],
+    start: Instant,
+    /// The tick (ms since `start`) the wheel has most recently advanced to.
+    now: u64,
+}
+
+struct Level<T> {
+    /// This level's index within the wheel, i.e. how many `SLOT_BITS` groups
+    /// of the deadline are consumed by the finer levels below it.
+    index: usize,
+    slots: Vec<Vec<Entry<T>>>,
+    /// Bit `i` is set iff `slots[i]` is non-empty.
+    occupied: u64,
+}
+
+struct Entry<T> {
+    deadline: u64,
+    value: T,
+}
+
+// ===== impl TimingWheel =====
+
+impl<T> TimingWheel<T> {
+    pub fn new<N: Now>(now: &N) -> Self {
+        TimingWheel {
+            levels: [
+                Level::new(0),
+                Level::new(1),
+                Level::new(2),
+                Level::new(3),
+                Level::new(4),
+                Level::new(5),
+            ],
+            start: now.now(),
+            now: 0,
+        }
+    }
+
+    /// Schedules `value` to expire at `deadline`.
+    ///
+    /// If `deadline` is already at or before the wheel's current tick, the
+    /// entry is already expired; rather than scheduling it (where it would
+    /// sit until the tick counter wraps back around to its slot), `value`
+    /// is simply handed back so the caller can treat it as expired now.
+    pub fn insert(&mut self, deadline: Instant, value: T) -> Option<T> {
+        let deadline = self.to_tick(deadline);
+        if deadline <= self.now {
+            return Some(value);
+        }
+        let level = self.level_for(deadline);
+        self.levels[level].insert(deadline, value);
+        None
+    }
+
+    /// Returns the instant of the earliest scheduled expiration, if any.
+    pub fn next_expiration(&self) -> Option<Instant> {
+        let now = self.now;
+        self.levels
+            .iter()
+            .filter_map(|level| level.next_deadline(now))
+            .min()
+            .map(|tick| self.start + Duration::from_millis(tick))
+    }
+
+    /// Advances the wheel to `now`, returning every entry whose deadline has
+    /// elapsed.
+    ///
+    /// As level 0 rolls over, entries held in the next level's current slot
+    /// are cascaded down into finer levels so that they eventually fire at
+    /// tick granularity.
+    pub fn poll<N: Now>(&mut self, now: &N) -> Vec<T> {
+        let target = self.to_tick(now.now());
+        let mut expired = Vec::new();
+        while self.now < target {
+            self.now += TICK_MS;
+            self.cascade();
+            expired.extend(
+                self.levels[0]
+                    .take_slot(self.now)
+                    .into_iter()
+                    .map(|entry| entry.value),
+            );
+        }
+        expired
+    }
+
+    /// Converts an absolute instant into a tick (ms since `start`).
+    fn to_tick(&self, instant: Instant) -> u64 {
+        instant.saturating_duration_since(self.start).as_millis() as u64
+    }
+
+    /// Returns the finest level whose span can still hold a deadline that is
+    /// `delta` ticks ahead of `self.now`.
+    fn level_for(&self, deadline: u64) -> usize {
+        let delta = deadline.saturating_sub(self.now);
+        for (level, _) in self.levels.iter().enumerate().take(LEVELS - 1) {
+            if delta < 1u64 << (SLOT_BITS * (level as u32 + 1)) {
+                return level;
+            }
+        }
+        LEVELS - 1
+    }
+
+    /// Re-inserts the entries of every level's current slot into whichever
+    /// finer level now fits their remaining delta (possibly several levels
+    /// down), for each level boundary that `self.now` just crossed.
+    fn cascade(&mut self) {
+        for level in 1..LEVELS {
+            if self.now & ((1 << (SLOT_BITS * level as u32)) - 1) != 0 {
+                break;
+            }
+            let entries = self.levels[level].take_slot(self.now);
+            for entry in entries {
+                let target = self.level_for(entry.deadline);
+                self.levels[target].insert(entry.deadline, entry.value);
+            }
+        }
+    }
+}
+
+// ===== impl Level =====
+
+impl<T> Level<T> {
+    fn new(index: usize) -> Self {
+        Level {
+            index,
+            slots: (0..SLOTS).map(|_| Vec::new()).collect(),
+            occupied: 0,
+        }
+    }
+
+    fn slot(&self, deadline: u64) -> usize {
+        ((deadline >> (SLOT_BITS * self.index as u32)) & (SLOTS as u64 - 1)) as usize
+    }
+
+    fn insert(&mut self, deadline: u64, value: T) {
+        let slot = self.slot(deadline);
+        if self.slots[slot].is_empty() {
+            self.occupied |= 1 << slot;
+        }
+        self.slots[slot].push(Entry { deadline, value });
+    }
+
+    fn take_slot(&mut self, deadline: u64) -> Vec<Entry<T>> {
+        let slot = self.slot(deadline);
+        self.occupied &= !(1 << slot);
+        std::mem::take(&mut self.slots[slot])
+    }
+
+    /// Returns the smallest deadline in the occupied slot nearest to `now`,
+    /// if any.
+    ///
+    /// Slot numbers wrap every `SLOTS` ticks, so the lowest-numbered
+    /// occupied slot isn't necessarily the nearest one: a deadline further
+    /// in the future can land in a numerically lower slot than one that's
+    /// due sooner. The occupied mask is rotated so the current slot is bit
+    /// 0 before scanning, making `trailing_zeros` report the nearest slot
+    /// rather than the lowest-numbered one.
+    fn next_deadline(&self, now: u64) -> Option<u64> {
+        if self.occupied == 0 {
+            return None;
+        }
+        let current = self.slot(now) as u32;
+        let nearest = self.occupied.rotate_right(current).trailing_zeros();
+        let slot = ((current + nearest) % SLOTS as u32) as usize;
+        self.slots[slot].iter().map(|e| e.deadline).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use test_util::*;
+
+    use super::*;
+
+    #[test]
+    fn fires_after_ttl_elapses() {
+        let mut clock = Clock::default();
+        let mut wheel = TimingWheel::new(&clock);
+
+        let deadline = clock.now() + Duration::from_millis(10);
+        wheel.insert(deadline, "a");
+
+        assert!(wheel.poll(&clock).is_empty());
+
+        clock.advance(Duration::from_millis(10));
+        assert_eq!(wheel.poll(&clock), vec!["a"]);
+    }
+
+    #[test]
+    fn cascades_across_levels() {
+        let mut clock = Clock::default();
+        let mut wheel = TimingWheel::new(&clock);
+
+        let deadline = clock.now() + Duration::from_millis(5000);
+        wheel.insert(deadline, "a");
+
+        clock.advance(Duration::from_millis(4999));
+        assert!(wheel.poll(&clock).is_empty());
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(wheel.poll(&clock), vec!["a"]);
+    }
+
+    #[test]
+    fn next_expiration_reports_soonest_deadline() {
+        let mut clock = Clock::default();
+        let mut wheel = TimingWheel::new(&clock);
+
+        assert!(wheel.next_expiration().is_none());
+
+        let soon = clock.now() + Duration::from_millis(50);
+        let later = clock.now() + Duration::from_millis(500);
+        wheel.insert(later, "later");
+        wheel.insert(soon, "soon");
+
+        assert_eq!(wheel.next_expiration(), Some(soon));
+    }
+
+    #[test]
+    fn next_expiration_accounts_for_slot_wraparound() {
+        let mut clock = Clock::default();
+        let mut wheel = TimingWheel::new(&clock);
+
+        // Advance the wheel's tick past a full revolution of level 0 so
+        // that the sooner deadline lands in a numerically *higher* slot
+        // than the later one (46 vs. 2), exercising the wraparound.
+        clock.advance(Duration::from_millis(100));
+        assert!(wheel.poll(&clock).is_empty());
+
+        let sooner = clock.now() + Duration::from_millis(10);
+        let later = clock.now() + Duration::from_millis(30);
+        wheel.insert(later, "later");
+        wheel.insert(sooner, "sooner");
+
+        assert_eq!(wheel.next_expiration(), Some(sooner));
+    }
+
+    #[test]
+    fn overdue_insert_fires_on_next_poll_instead_of_waiting_for_slot_wraparound() {
+        let mut clock = Clock::default();
+        let mut wheel = TimingWheel::new(&clock);
+
+        clock.advance(Duration::from_millis(1000));
+        assert!(wheel.poll(&clock).is_empty());
+
+        // A deadline that's already due should come straight back...
+        assert_eq!(wheel.insert(clock.now(), "overdue"), Some("overdue"));
+
+        // ...and a deadline one tick later fires on the very next poll, not
+        // up to `SLOTS - 1` ticks later.
+        assert_eq!(
+            wheel.insert(clock.now() + Duration::from_millis(1), "a"),
+            None
+        );
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(wheel.poll(&clock), vec!["a"]);
+    }
+}